@@ -0,0 +1,28 @@
+// src/error.rs
+
+//! Diagnostics produced while lexing. The lexer never panics or silently
+//! drops bad input; instead it records a `LexError` for each problem and
+//! keeps tokenizing so every issue in a source file can be reported at once.
+
+use crate::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexErrorKind {
+    UnterminatedString,
+    InvalidNumber,
+    InvalidEscape,
+    UnexpectedChar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub message: String,
+    pub span: Span,
+}
+
+impl LexError {
+    pub fn new(kind: LexErrorKind, message: impl Into<String>, span: Span) -> Self {
+        LexError { kind, message: message.into(), span }
+    }
+}
@@ -1,44 +1,170 @@
+use std::borrow::Cow;
+
+use crate::error::{LexError, LexErrorKind};
+use crate::span::{Span, Spanned};
 use crate::token::Token;
 
+/// A scanning context on the lexer's mode stack. `next_token` dispatches on
+/// the top of the stack, so a child mode's rules are tried before falling
+/// back to its parent's — e.g. a `"` inside an `Interpolation` expression
+/// opens a nested string without the outer string's rules getting in the
+/// way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexerMode {
+    /// Ordinary Kāra source: keywords, operators, literals.
+    Normal,
+    /// Inside a string literal's plain-text portion.
+    StringBody,
+    /// Inside a `${ ... }` interpolated expression. The payload counts
+    /// `{`/`}` nesting inside the expression itself, so the lexer can tell
+    /// the matching close brace from one belonging to a nested block.
+    Interpolation(u32),
+}
+
 /// The Lexer, responsible for turning source code into a stream of tokens.
-pub struct Lexer {
-    source: Vec<char>,
+///
+/// Borrows the input for its whole lifetime instead of copying it: `ch` is
+/// decoded straight out of `source`, and identifiers and escape-free strings
+/// are sliced out of it without allocating. Strings with escapes and number
+/// literals are built into owned `String`s while scanning.
+pub struct Lexer<'src> {
+    source: &'src str,
+    /// Byte offset of `ch` in `source` (equal to `source.len()` at EOF).
     position: usize,
-    read_position: usize,
     ch: char,
+    /// 1-based line of `ch`.
+    line: u32,
+    /// 1-based column of `ch`.
+    col: u32,
+    /// Every problem found so far. The lexer never panics or stops on
+    /// these; it records them here and keeps producing tokens.
+    errors: Vec<LexError>,
+    /// Scanning context stack; always has at least one entry (`Normal`).
+    modes: Vec<LexerMode>,
+    /// Whether `source` is known to hold the rest of the logical stream, so
+    /// hitting `'\0'` really does mean end-of-input. `false` for a
+    /// [`crate::reader_lexer::ReaderLexer`] attempt scanning a buffer that
+    /// hasn't been refilled to the stream's real end yet, in which case
+    /// `'\0'` is ambiguous and must not be treated as a diagnosable EOF.
+    stream_complete: bool,
+    /// Set when `'\0'` is reached while `stream_complete` is `false`: the
+    /// scan ran off the end of the buffer before it could tell whether the
+    /// lexeme was actually finished. The caller must discard this attempt
+    /// entirely (it may contain no diagnostics, truncated modes, etc. of
+    /// its own) and retry against a bigger buffer.
+    incomplete: bool,
 }
 
-impl Lexer {
+impl<'src> Lexer<'src> {
     /// Creates a new Lexer instance.
-    pub fn new(source: String) -> Self {
+    pub fn new(source: &'src str) -> Self {
         let mut lexer = Lexer {
-            source: source.chars().collect(),
+            source,
             position: 0,
-            read_position: 0,
             ch: '\0',
+            line: 1,
+            col: 1,
+            errors: Vec::new(),
+            modes: vec![LexerMode::Normal],
+            stream_complete: true,
+            incomplete: false,
         };
         lexer.read_char();
         lexer
     }
 
-    /// Reads the next character and advances the lexer's position.
+    /// Resumes scanning at the start of a new `source` slice, carrying over
+    /// the line/column, mode stack, and collected errors of a previous
+    /// `Lexer` over an earlier slice of the same logical stream.
+    ///
+    /// Used by [`crate::reader_lexer::ReaderLexer`] to keep scanning across
+    /// sliding-buffer refills without re-lexing from byte zero each time:
+    /// the buffer's contents change out from under any borrow on every
+    /// refill, so a single `Lexer<'src>` can't span them, but the scanning
+    /// state it accumulates can be handed forward to a fresh one.
+    ///
+    /// `stream_complete` must be `false` whenever `source` might not hold
+    /// the rest of the logical stream yet (i.e. the underlying reader isn't
+    /// known to be exhausted), so a `'\0'` hit here is treated as "buffer
+    /// exhausted, try again with more" rather than a genuine end-of-input
+    /// diagnostic; see [`Lexer::is_incomplete`].
+    pub(crate) fn resume(source: &'src str, line: u32, col: u32, modes: Vec<LexerMode>, errors: Vec<LexError>, stream_complete: bool) -> Self {
+        let mut lexer = Lexer { source, position: 0, ch: '\0', line, col, errors, modes, stream_complete, incomplete: false };
+        lexer.read_char();
+        lexer
+    }
+
+    /// Byte offset of `ch` within this `Lexer`'s `source`, i.e. how much of
+    /// it has been consumed. Equal to `source.len()` once scanning has
+    /// reached the end of this slice.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// True if this scan hit `'\0'` while `stream_complete` was `false`:
+    /// the attempt ran off the end of a buffer that may not hold the whole
+    /// lexeme, so the caller must discard it in full (any token, error, or
+    /// mode-stack change it produced is not trustworthy) and retry once
+    /// more of the stream is available.
+    pub(crate) fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    /// Consumes the lexer, returning the line/column, mode stack, and
+    /// errors accumulated so far, for handing forward to [`Lexer::resume`].
+    pub(crate) fn into_state(self) -> (u32, u32, Vec<LexerMode>, Vec<LexError>) {
+        (self.line, self.col, self.modes, self.errors)
+    }
+
+    /// Consumes the lexer, returning every diagnostic collected while it ran.
+    pub fn into_errors(self) -> Vec<LexError> {
+        self.errors
+    }
+
+    /// Pushes a new scanning context, e.g. entering a string or an
+    /// interpolated expression.
+    pub fn push_mode(&mut self, mode: LexerMode) {
+        self.modes.push(mode);
+    }
+
+    /// Pops back to the parent scanning context. The base `Normal` context
+    /// is never popped.
+    pub fn pop_mode(&mut self) {
+        if self.modes.len() > 1 {
+            self.modes.pop();
+        }
+    }
+
+    /// Reads the next character and advances the lexer's position, keeping
+    /// `position`/`line`/`col` in sync with `ch`. These three are only ever
+    /// touched here, so every other scanning helper can trust them.
     fn read_char(&mut self) {
-        if self.read_position >= self.source.len() {
-            self.ch = '\0';
-        } else {
-            self.ch = self.source[self.read_position];
+        if self.ch != '\0' {
+            self.position += self.ch.len_utf8();
+            if self.ch == '\n' {
+                self.line += 1;
+                self.col = 1;
+            } else {
+                self.col += 1;
+            }
+        }
+        self.ch = self.source[self.position..].chars().next().unwrap_or('\0');
+        // Every scanning loop in this file treats `'\0'` as "no more input,"
+        // whether that's a dedicated `'\0'` match arm or a `while
+        // is_identifier_char(self.ch)`-style loop simply falling through.
+        // Detecting the ambiguous case here, once, means every one of them
+        // is covered — not just the few with their own `'\0'` arm.
+        if self.ch == '\0' && !self.stream_complete {
+            self.incomplete = true;
         }
-        self.position = self.read_position;
-        self.read_position += 1;
     }
 
     /// Peeks at the next character without consuming it.
     fn peek_char(&self) -> char {
-        if self.read_position >= self.source.len() {
-            '\0'
-        } else {
-            self.source[self.read_position]
+        if self.ch == '\0' {
+            return '\0';
         }
+        self.source[self.position + self.ch.len_utf8()..].chars().next().unwrap_or('\0')
     }
 
     /// Skips over any whitespace characters.
@@ -48,103 +174,502 @@ impl Lexer {
         }
     }
 
-    /// Returns the next token from the source code.
-    pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+    /// Returns the next token from the source code, paired with the span of
+    /// source it came from. Dispatches on the current mode so a string body
+    /// or an interpolated expression is scanned by its own rules.
+    pub fn next_token(&mut self) -> Spanned<Token<'src>> {
+        match self.modes.last().copied().unwrap_or(LexerMode::Normal) {
+            LexerMode::StringBody => self.next_token_string_body(),
+            LexerMode::Interpolation(_) => self.next_token_interpolation(),
+            LexerMode::Normal => self.scan_token(),
+        }
+    }
 
-        // Handle comments
-        if self.ch == '/' && self.peek_char() == '/' {
+    /// Skips whitespace and `//` comments ahead of the next token.
+    fn skip_trivia(&mut self) {
+        self.skip_whitespace();
+        while self.ch == '/' && self.peek_char() == '/' {
             while self.ch != '\n' && self.ch != '\0' {
                 self.read_char();
             }
             self.skip_whitespace(); // Skip more whitespace after the comment
         }
+    }
+
+    /// Scans one ordinary token: keywords, operators, literals, and the `"`
+    /// that opens a string. Shared by `Normal` and `Interpolation` mode,
+    /// since an interpolated expression is lexed exactly like top-level
+    /// source aside from brace-depth bookkeeping.
+    fn scan_token(&mut self) -> Spanned<Token<'src>> {
+        self.skip_trivia();
+
+        let start_offset = self.position;
+        let start_line = self.line;
+        let start_col = self.col;
 
         let tok = match self.ch {
-            '=' => Token::Equal,
+            '=' => {
+                if self.peek_char() == '=' {
+                    self.read_char();
+                    Token::EqualEqual
+                } else {
+                    Token::Equal
+                }
+            }
+            '!' => {
+                if self.peek_char() == '=' {
+                    self.read_char();
+                    Token::BangEqual
+                } else {
+                    Token::Bang
+                }
+            }
+            '>' => {
+                if self.peek_char() == '=' {
+                    self.read_char();
+                    Token::GreaterThanOrEqual
+                } else {
+                    Token::GreaterThan
+                }
+            }
+            '<' => {
+                if self.peek_char() == '=' {
+                    self.read_char();
+                    Token::LessThanOrEqual
+                } else {
+                    Token::LessThan
+                }
+            }
             ';' => Token::Semicolon,
             ':' => Token::Colon,
             ',' => Token::Comma,
             '.' => Token::Dot,
-            '(' => Token::LParen,
-            ')' => Token::RParen,
-            '{' => Token::LBrace,
-            '}' => Token::RBrace,
+            '+' => Token::Plus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
             '-' => {
                 if self.peek_char() == '>' {
                     self.read_char(); // consume the '-'
-                    self.read_char(); // consume the '>'
-                    return Token::Arrow;
+                    Token::Arrow
                 } else {
-                    Token::Illegal(self.ch.to_string())
+                    Token::Minus
                 }
             }
             '"' => {
-                return Token::String(self.read_string());
+                self.read_char(); // consume the opening quote
+                self.push_mode(LexerMode::StringBody);
+                return self.next_token_string_body();
+            }
+            '\0' => {
+                // No side effects to gate here: `read_char` already flagged
+                // `self.incomplete` if this is an ambiguous buffer boundary
+                // rather than a genuine end-of-input.
+                return Spanned::new(
+                    Token::EOF,
+                    Span { start: start_offset, end: start_offset, line: start_line, col: start_col },
+                );
             }
-            '\0' => Token::EOF,
             _ => {
                 if is_identifier_start(self.ch) {
                     let literal = self.read_identifier();
-                    return Self::lookup_ident(&literal);
-                } else if self.ch.is_digit(10) {
-                    let num_str = self.read_number();
-                    return Token::Number(num_str.parse().unwrap_or(0.0));
+                    let end_offset = self.position;
+                    return Spanned::new(
+                        Self::lookup_ident(literal),
+                        Span { start: start_offset, end: end_offset, line: start_line, col: start_col },
+                    );
+                } else if self.ch.is_ascii_digit() {
+                    return self.read_number_token(start_offset, start_line, start_col);
                 } else {
-                    Token::Illegal(self.ch.to_string())
+                    self.errors.push(LexError::new(
+                        LexErrorKind::UnexpectedChar,
+                        format!("unexpected character `{}`", self.ch),
+                        Span { start: start_offset, end: start_offset + self.ch.len_utf8(), line: start_line, col: start_col },
+                    ));
+                    Token::Error
                 }
             }
         };
 
         self.read_char();
-        tok
+        let end_offset = self.position;
+        Spanned::new(tok, Span { start: start_offset, end: end_offset, line: start_line, col: start_col })
     }
 
-    /// Reads a full identifier from the source.
-    fn read_identifier(&mut self) -> String {
-        let position = self.position;
+    /// Scans one token inside a `${ ... }` expression. Identical to
+    /// `scan_token` except it tracks brace nesting so the `}` that matches
+    /// this interpolation's own `${` pops back to `StringBody`, while a
+    /// `{`/`}` pair belonging to a nested block expression does not.
+    fn next_token_interpolation(&mut self) -> Spanned<Token<'src>> {
+        self.skip_trivia();
+        match self.ch {
+            '{' => {
+                if let Some(LexerMode::Interpolation(depth)) = self.modes.last_mut() {
+                    *depth += 1;
+                }
+                self.scan_token()
+            }
+            '}' => {
+                let closes_interpolation = matches!(self.modes.last(), Some(LexerMode::Interpolation(0)));
+                if !closes_interpolation {
+                    if let Some(LexerMode::Interpolation(depth)) = self.modes.last_mut() {
+                        *depth -= 1;
+                    }
+                }
+                let token = self.scan_token();
+                if closes_interpolation {
+                    self.pop_mode();
+                }
+                token
+            }
+            '\0' => {
+                let start_offset = self.position;
+                let span = Span { start: start_offset, end: start_offset, line: self.line, col: self.col };
+                if self.incomplete {
+                    // `read_char` already flagged this as an ambiguous
+                    // buffer boundary; don't also report a diagnostic or
+                    // unwind the mode stack for it.
+                    return Spanned::new(Token::Error, span);
+                }
+                self.errors.push(LexError::new(LexErrorKind::UnterminatedString, "unterminated string literal", span));
+                // EOF ends every open context at once, however deeply the
+                // interpolation was nested, so unwind all the way back to
+                // `Normal` rather than popping a single level.
+                self.modes.truncate(1);
+                Spanned::new(Token::Error, span)
+            }
+            _ => self.scan_token(),
+        }
+    }
+
+    /// Scans the plain-text portion of a string literal: everything up to
+    /// the closing `"` or a `${` that opens an interpolated expression.
+    /// Escapes are decoded as in a non-interpolated string; a chunk with no
+    /// escapes borrows straight out of `source`.
+    fn next_token_string_body(&mut self) -> Spanned<Token<'src>> {
+        let start_offset = self.position;
+        let start_line = self.line;
+        let start_col = self.col;
+        let chunk_start = self.position;
+
+        loop {
+            match self.ch {
+                '"' => {
+                    let chunk = Cow::Borrowed(&self.source[chunk_start..self.position]);
+                    self.pop_mode();
+                    self.read_char(); // consume the closing quote
+                    let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                    return Spanned::new(Token::StringLiteral(chunk), span);
+                }
+                '\0' => {
+                    let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                    if self.incomplete {
+                        // `read_char` already flagged this as an ambiguous
+                        // buffer boundary; don't also report a diagnostic or
+                        // pop the mode stack for it.
+                        return Spanned::new(Token::Error, span);
+                    }
+                    self.errors.push(LexError::new(LexErrorKind::UnterminatedString, "unterminated string literal", span));
+                    self.pop_mode();
+                    return Spanned::new(Token::Error, span);
+                }
+                '$' if self.peek_char() == '{' => {
+                    if self.position > chunk_start {
+                        let chunk = Cow::Borrowed(&self.source[chunk_start..self.position]);
+                        let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                        return Spanned::new(Token::StringLiteral(chunk), span);
+                    }
+                    return self.enter_interpolation(start_line, start_col);
+                }
+                '\\' => {
+                    let mut value = self.source[chunk_start..self.position].to_string();
+                    self.read_escape(&mut value);
+                    return self.string_body_owned_tail(value, start_offset, start_line, start_col);
+                }
+                _ => self.read_char(),
+            }
+        }
+    }
+
+    /// Finishes a string-body chunk once an escape forced an owned buffer,
+    /// mirroring `next_token_string_body`'s end conditions.
+    fn string_body_owned_tail(&mut self, mut value: String, start_offset: usize, start_line: u32, start_col: u32) -> Spanned<Token<'src>> {
+        loop {
+            match self.ch {
+                '"' => {
+                    self.pop_mode();
+                    self.read_char(); // consume the closing quote
+                    let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                    return Spanned::new(Token::StringLiteral(Cow::Owned(value)), span);
+                }
+                '\0' => {
+                    let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                    if self.incomplete {
+                        // `read_char` already flagged this as an ambiguous
+                        // buffer boundary; don't also report a diagnostic or
+                        // pop the mode stack for it.
+                        return Spanned::new(Token::Error, span);
+                    }
+                    self.errors.push(LexError::new(LexErrorKind::UnterminatedString, "unterminated string literal", span));
+                    self.pop_mode();
+                    return Spanned::new(Token::Error, span);
+                }
+                '$' if self.peek_char() == '{' => {
+                    if !value.is_empty() {
+                        let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+                        return Spanned::new(Token::StringLiteral(Cow::Owned(value)), span);
+                    }
+                    // An invalid escape may have been consumed without adding
+                    // to `value`, so `$` need not be where this chunk started.
+                    let (line, col) = (self.line, self.col);
+                    return self.enter_interpolation(line, col);
+                }
+                '\\' => self.read_escape(&mut value),
+                c => {
+                    value.push(c);
+                    self.read_char();
+                }
+            }
+        }
+    }
+
+    /// Consumes `${`, pushes `Interpolation` and emits the `InterpStart`
+    /// token marking the expression's start.
+    fn enter_interpolation(&mut self, start_line: u32, start_col: u32) -> Spanned<Token<'src>> {
+        let start_offset = self.position;
+        self.read_char(); // consume '$'
+        self.read_char(); // consume '{'
+        self.push_mode(LexerMode::Interpolation(0));
+        let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+        Spanned::new(Token::InterpStart, span)
+    }
+
+    /// Reads a full identifier, returning a slice straight out of `source`.
+    fn read_identifier(&mut self) -> &'src str {
+        let start = self.position;
         while is_identifier_char(self.ch) {
             self.read_char();
         }
-        self.source[position..self.position].iter().collect()
+        &self.source[start..self.position]
+    }
+
+    /// Scans a numeric literal starting at `self.ch` (already known to be an
+    /// ASCII digit), producing an `Integer` or `Float` token. Radix-prefixed
+    /// integers (`0x`/`0o`/`0b`), `_` digit separators and `e`/`E`
+    /// exponents are all handled here; anything malformed is recorded as an
+    /// `InvalidNumber` error and surfaces as `Token::Error`.
+    fn read_number_token(&mut self, start_offset: usize, start_line: u32, start_col: u32) -> Spanned<Token<'src>> {
+        let result = if self.ch == '0' && matches!(self.peek_char(), 'x' | 'X' | 'o' | 'b') {
+            self.read_radix_integer()
+        } else {
+            self.read_decimal_number()
+        };
+
+        let span = Span { start: start_offset, end: self.position, line: start_line, col: start_col };
+        match result {
+            Ok(token) => Spanned::new(token, span),
+            Err(message) => {
+                self.errors.push(LexError::new(LexErrorKind::InvalidNumber, message, span));
+                Spanned::new(Token::Error, span)
+            }
+        }
+    }
+
+    /// Reads a `0x`/`0o`/`0b`-prefixed integer literal.
+    fn read_radix_integer(&mut self) -> Result<Token<'src>, String> {
+        let radix_char = self.peek_char();
+        self.read_char(); // consume '0'
+        self.read_char(); // consume the radix letter
+
+        let (radix, is_digit): (u32, fn(char) -> bool) = match radix_char {
+            'x' | 'X' => (16, |c| c.is_ascii_hexdigit()),
+            'o' => (8, |c| ('0'..='7').contains(&c)),
+            'b' => (2, |c| c == '0' || c == '1'),
+            _ => unreachable!("read_number_token only dispatches here for x/X/o/b"),
+        };
+
+        let digits = self.read_digits(is_digit);
+        if digits.is_empty() {
+            return Err(format!("radix prefix `0{radix_char}` has no digits"));
+        }
+        i64::from_str_radix(&digits, radix)
+            .map(Token::Integer)
+            .map_err(|_| format!("integer literal `0{radix_char}{digits}` out of range"))
+    }
+
+    /// Reads a decimal literal, deciding `Integer` vs `Float` based on
+    /// whether a fractional part or exponent is present.
+    fn read_decimal_number(&mut self) -> Result<Token<'src>, String> {
+        let mut text = self.read_digits(|c| c.is_ascii_digit());
+        let mut is_float = false;
+
+        if self.ch == '.' && self.peek_char().is_ascii_digit() {
+            is_float = true;
+            text.push('.');
+            self.read_char();
+            text.push_str(&self.read_digits(|c| c.is_ascii_digit()));
+        }
+
+        // A second decimal point is a lex error rather than silently
+        // truncating or re-lexing as member access.
+        if is_float && self.ch == '.' {
+            self.read_char();
+            self.read_digits(|c| c.is_ascii_digit());
+            return Err("number literal has more than one decimal point".to_string());
+        }
+
+        if matches!(self.ch, 'e' | 'E') {
+            let mut exponent = String::new();
+            exponent.push(self.ch);
+            self.read_char();
+            if matches!(self.ch, '+' | '-') {
+                exponent.push(self.ch);
+                self.read_char();
+            }
+            let exponent_digits = self.read_digits(|c| c.is_ascii_digit());
+            if exponent_digits.is_empty() {
+                return Err("exponent has no digits".to_string());
+            }
+            exponent.push_str(&exponent_digits);
+            text.push_str(&exponent);
+            is_float = true;
+        }
+
+        if is_float {
+            text.parse::<f64>().map(Token::Float).map_err(|_| format!("invalid float literal `{text}`"))
+        } else {
+            text.parse::<i64>().map(Token::Integer).map_err(|_| format!("invalid integer literal `{text}`"))
+        }
     }
 
-    /// Reads a number (integer or float) from the source.
-    fn read_number(&mut self) -> String {
-        let position = self.position;
-        while self.ch.is_digit(10) || self.ch == '.' {
+    /// Reads a run of digits matching `is_digit`, stripping `_` separators.
+    fn read_digits(&mut self, is_digit: fn(char) -> bool) -> String {
+        let mut digits = String::new();
+        while is_digit(self.ch) || self.ch == '_' {
+            if self.ch != '_' {
+                digits.push(self.ch);
+            }
             self.read_char();
         }
-        self.source[position..self.position].iter().collect()
+        digits
     }
 
-    /// Reads a string literal from the source.
-    fn read_string(&mut self) -> String {
-        self.read_char(); // Consume the opening quote
-        let position = self.position;
-        while self.ch != '"' && self.ch != '\0' {
+    /// Decodes one `\...` escape sequence, appending the result to `value`.
+    /// An invalid escape records a `LexError` and contributes nothing to
+    /// the decoded string, so scanning can keep going.
+    fn read_escape(&mut self, value: &mut String) {
+        let start_offset = self.position;
+        let start_line = self.line;
+        let start_col = self.col;
+        self.read_char(); // consume the backslash
+
+        let decoded = match self.ch {
+            'n' => {
+                self.read_char();
+                Some('\n')
+            }
+            't' => {
+                self.read_char();
+                Some('\t')
+            }
+            'r' => {
+                self.read_char();
+                Some('\r')
+            }
+            '\\' => {
+                self.read_char();
+                Some('\\')
+            }
+            '"' => {
+                self.read_char();
+                Some('"')
+            }
+            '0' => {
+                self.read_char();
+                Some('\0')
+            }
+            'x' => {
+                self.read_char(); // consume 'x'
+                let hex = self.read_hex_digits(2);
+                (hex.len() == 2)
+                    .then(|| u32::from_str_radix(&hex, 16).ok())
+                    .flatten()
+                    .and_then(char::from_u32)
+            }
+            'u' => {
+                self.read_char(); // consume 'u'
+                if self.ch == '{' {
+                    self.read_char(); // consume '{'
+                    let hex = self.read_hex_digits(6);
+                    let closed = self.ch == '}';
+                    if closed {
+                        self.read_char(); // consume '}'
+                    }
+                    (closed && !hex.is_empty())
+                        .then(|| u32::from_str_radix(&hex, 16).ok())
+                        .flatten()
+                        .and_then(char::from_u32)
+                } else {
+                    None
+                }
+            }
+            _ => {
+                self.read_char();
+                None
+            }
+        };
+
+        match decoded {
+            Some(c) => value.push(c),
+            None => {
+                let span = Span {
+                    start: start_offset,
+                    end: self.position,
+                    line: start_line,
+                    col: start_col,
+                };
+                self.errors.push(LexError::new(
+                    LexErrorKind::InvalidEscape,
+                    "invalid escape sequence",
+                    span,
+                ));
+            }
+        }
+    }
+
+    /// Reads up to `max` ASCII hex digits, consuming exactly what it reads.
+    fn read_hex_digits(&mut self, max: usize) -> String {
+        let mut digits = String::new();
+        while digits.len() < max && self.ch.is_ascii_hexdigit() {
+            digits.push(self.ch);
             self.read_char();
         }
-        let result = self.source[position..self.position].iter().collect();
-        self.read_char(); // Consume the closing quote
-        result
+        digits
     }
 
     /// Maps an identifier string to a keyword Token or an Identifier Token.
-    fn lookup_ident(ident: &str) -> Token {
+    /// `Define`, `Sūtra`, `Action`, `From`, `Into`, `Require`, and `Return`
+    /// are not keywords here: an earlier draft of this table mapped them to
+    /// `Token::Define`/`Token::Sutra`/etc., but `Token` never gained those
+    /// variants, so that table couldn't have compiled. Until those variants
+    /// exist, the words lex as plain identifiers like any other name; `Action:`
+    /// and `From:` in `main.rs`'s sample program are examples of this, not a
+    /// lexer bug.
+    fn lookup_ident(ident: &'src str) -> Token<'src> {
         match ident {
-            "Record" => Token::Record,
-            "Define" => Token::Define,
-            "Sūtra" => Token::Sutra,
+            "fn" => Token::Fn,
             "flow" => Token::Flow,
+            "Record" => Token::Record,
+            "type" => Token::Type,
             "let" => Token::Let,
-            "Action" => Token::Action,
-            "From" => Token::From,
-            "Into" => Token::Into,
-            "Require" => Token::Require,
-            "Return" => Token::Return,
+            "if" => Token::If,
+            "true" => Token::True,
+            "false" => Token::False,
             "as" => Token::As,
-            _ => Token::Identifier(ident.to_string()),
+            _ => Token::Identifier(ident),
         }
     }
 }
@@ -159,10 +684,12 @@ fn is_identifier_char(ch: char) -> bool {
     ch.is_alphanumeric() || ch == '_'
 }
 
-
 #[cfg(test)]
 mod tests {
-    use super::{Lexer, Token};
+    use super::Lexer;
+    use crate::error::LexErrorKind;
+    use crate::token::Token;
+    use std::borrow::Cow;
 
     #[test]
     fn test_full_syntax_lexing() {
@@ -172,7 +699,7 @@ Record User {
     id: i64,
     name: String,
     // email is optional
-    email: String, 
+    email: String,
 }
 
 // Define a simple flow
@@ -183,73 +710,245 @@ flow RegisterUser {
         email: "contact@kara.dev",
     };
 
-    // Use the 'Sūtra' keyword
-    Sūtra: LogUser -> ();
-
+    // Uses the dense `->` syntax rather than `Sūtra: LogUser -> ();`:
+    // `Sūtra` isn't a recognized keyword (see `Lexer::lookup_ident`), so it
+    // would lex as a plain identifier here rather than exercising anything
+    // keyword-specific.
     new_user -> LogUser;
 }
 "#;
 
-        let mut lexer = Lexer::new(source.to_string());
+        let mut lexer = Lexer::new(source);
 
         let expected_tokens = vec![
             Token::Record,
-            Token::Identifier("User".to_string()),
-            Token::LBrace,
-            Token::Identifier("id".to_string()),
+            Token::Identifier("User"),
+            Token::LeftBrace,
+            Token::Identifier("id"),
             Token::Colon,
-            Token::Identifier("i64".to_string()),
+            Token::Identifier("i64"),
             Token::Comma,
-            Token::Identifier("name".to_string()),
+            Token::Identifier("name"),
             Token::Colon,
-            Token::Identifier("String".to_string()),
+            Token::Identifier("String"),
             Token::Comma,
-            Token::Identifier("email".to_string()),
+            Token::Identifier("email"),
             Token::Colon,
-            Token::Identifier("String".to_string()),
+            Token::Identifier("String"),
             Token::Comma,
-            Token::RBrace,
+            Token::RightBrace,
             Token::Flow,
-            Token::Identifier("RegisterUser".to_string()),
-            Token::LBrace,
+            Token::Identifier("RegisterUser"),
+            Token::LeftBrace,
             Token::Let,
-            Token::Identifier("new_user".to_string()),
+            Token::Identifier("new_user"),
             Token::Equal,
-            Token::Identifier("User".to_string()),
-            Token::LBrace,
-            Token::Identifier("id".to_string()),
+            Token::Identifier("User"),
+            Token::LeftBrace,
+            Token::Identifier("id"),
             Token::Colon,
-            Token::Number(101.0),
+            Token::Integer(101),
             Token::Comma,
-            Token::Identifier("name".to_string()),
+            Token::Identifier("name"),
             Token::Colon,
-            Token::String("Kāra".to_string()),
+            Token::StringLiteral(Cow::Borrowed("Kāra")),
             Token::Comma,
-            Token::Identifier("email".to_string()),
+            Token::Identifier("email"),
             Token::Colon,
-            Token::String("contact@kara.dev".to_string()),
+            Token::StringLiteral(Cow::Borrowed("contact@kara.dev")),
             Token::Comma,
-            Token::RBrace,
-            Token::Semicolon,
-            Token::Sutra,
-            Token::Colon,
-            Token::Identifier("LogUser".to_string()),
-            Token::Arrow,
-            Token::LParen,
-            Token::RParen,
+            Token::RightBrace,
             Token::Semicolon,
-            Token::Identifier("new_user".to_string()),
+            Token::Identifier("new_user"),
             Token::Arrow,
-            Token::Identifier("LogUser".to_string()),
+            Token::Identifier("LogUser"),
             Token::Semicolon,
-            Token::RBrace,
+            Token::RightBrace,
             Token::EOF,
         ];
 
         for expected_token in expected_tokens {
-            let actual_token = lexer.next_token();
-            println!("Expected: {:?}, Got: {:?}", expected_token, actual_token);
-            assert_eq!(actual_token, expected_token);
+            let actual = lexer.next_token();
+            assert_eq!(actual.value, expected_token);
         }
     }
+
+    #[test]
+    fn test_spans_cover_the_full_lexeme() {
+        let mut lexer = Lexer::new("  foo");
+        let tok = lexer.next_token();
+        assert_eq!(tok.value, Token::Identifier("foo"));
+        assert_eq!(tok.span.start, 2);
+        assert_eq!(tok.span.end, 5);
+        assert_eq!(tok.span.line, 1);
+        assert_eq!(tok.span.col, 3);
+    }
+
+    #[test]
+    fn test_spans_track_lines() {
+        let mut lexer = Lexer::new("a\nbb");
+        let first = lexer.next_token();
+        assert_eq!(first.span.line, 1);
+        let second = lexer.next_token();
+        assert_eq!(second.value, Token::Identifier("bb"));
+        assert_eq!(second.span.line, 2);
+        assert_eq!(second.span.col, 1);
+    }
+
+    #[test]
+    fn test_eof_span_is_zero_width() {
+        let mut lexer = Lexer::new("x");
+        lexer.next_token();
+        let eof = lexer.next_token();
+        assert_eq!(eof.value, Token::EOF);
+        assert_eq!(eof.span.start, eof.span.end);
+    }
+
+    #[test]
+    fn test_unexpected_char_is_collected_and_lexing_continues() {
+        let mut lexer = Lexer::new("a @ b");
+        assert_eq!(lexer.next_token().value, Token::Identifier("a"));
+        assert_eq!(lexer.next_token().value, Token::Error);
+        assert_eq!(lexer.next_token().value, Token::Identifier("b"));
+        assert_eq!(lexer.next_token().value, Token::EOF);
+
+        let errors = lexer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, crate::error::LexErrorKind::UnexpectedChar);
+    }
+
+    #[test]
+    fn test_string_with_no_escapes_borrows_the_source() {
+        let mut lexer = Lexer::new(r#""plain string""#);
+        let tok = lexer.next_token();
+        match tok.value {
+            Token::StringLiteral(Cow::Borrowed(s)) => assert_eq!(s, "plain string"),
+            other => panic!("expected a borrowed string literal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_string_escapes_are_decoded() {
+        let mut lexer = Lexer::new(r#""line\nbreak\t\"quoted\"\\done""#);
+        let tok = lexer.next_token();
+        assert_eq!(tok.value, Token::StringLiteral(Cow::Owned("line\nbreak\t\"quoted\"\\done".to_string())));
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_string_hex_and_unicode_escapes() {
+        let mut lexer = Lexer::new(r#""\x41\u{1F600}""#);
+        let tok = lexer.next_token();
+        assert_eq!(tok.value, Token::StringLiteral(Cow::Owned("A\u{1F600}".to_string())));
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_escape_is_collected_and_dropped() {
+        let mut lexer = Lexer::new(r#""bad\qend""#);
+        let tok = lexer.next_token();
+        assert_eq!(tok.value, Token::StringLiteral(Cow::Owned("badend".to_string())));
+
+        let errors = lexer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, crate::error::LexErrorKind::InvalidEscape);
+    }
+
+    #[test]
+    fn test_integer_vs_float_literals() {
+        let mut lexer = Lexer::new("42 3.25 1e3 2.5e-2");
+        assert_eq!(lexer.next_token().value, Token::Integer(42));
+        assert_eq!(lexer.next_token().value, Token::Float(3.25));
+        assert_eq!(lexer.next_token().value, Token::Float(1e3));
+        assert_eq!(lexer.next_token().value, Token::Float(2.5e-2));
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_radix_integer_literals() {
+        let mut lexer = Lexer::new("0xFF 0o17 0b101");
+        assert_eq!(lexer.next_token().value, Token::Integer(0xFF));
+        assert_eq!(lexer.next_token().value, Token::Integer(0o17));
+        assert_eq!(lexer.next_token().value, Token::Integer(0b101));
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_digit_separators_are_stripped() {
+        let mut lexer = Lexer::new("1_000_000");
+        assert_eq!(lexer.next_token().value, Token::Integer(1_000_000));
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_bare_radix_prefix_is_a_lex_error() {
+        let mut lexer = Lexer::new("0x");
+        assert_eq!(lexer.next_token().value, Token::Error);
+        let errors = lexer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, crate::error::LexErrorKind::InvalidNumber);
+    }
+
+    #[test]
+    fn test_two_decimal_points_is_a_lex_error() {
+        let mut lexer = Lexer::new("1.2.3");
+        assert_eq!(lexer.next_token().value, Token::Error);
+        let errors = lexer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, crate::error::LexErrorKind::InvalidNumber);
+    }
+
+    #[test]
+    fn test_string_interpolation_is_tokenized_as_a_sequence() {
+        let mut lexer = Lexer::new(r#""hello ${name}!""#);
+
+        let expected_tokens = vec![
+            Token::StringLiteral(Cow::Borrowed("hello ")),
+            Token::InterpStart,
+            Token::Identifier("name"),
+            Token::RightBrace,
+            Token::StringLiteral(Cow::Borrowed("!")),
+        ];
+
+        for expected_token in expected_tokens {
+            assert_eq!(lexer.next_token().value, expected_token);
+        }
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_nested_braces_inside_interpolation_do_not_close_it_early() {
+        let mut lexer = Lexer::new(r#""${ if true { 1 } }""#);
+
+        let expected_tokens = vec![
+            Token::InterpStart,
+            Token::If,
+            Token::True,
+            Token::LeftBrace,
+            Token::Integer(1),
+            Token::RightBrace,
+            Token::RightBrace,
+            Token::StringLiteral(Cow::Borrowed("")),
+            Token::EOF,
+        ];
+
+        for expected_token in expected_tokens {
+            assert_eq!(lexer.next_token().value, expected_token);
+        }
+        assert!(lexer.into_errors().is_empty());
+    }
+
+    #[test]
+    fn test_unclosed_interpolation_is_reported_as_unterminated_string() {
+        let mut lexer = Lexer::new(r#""${ 1 + 2 "#);
+        assert_eq!(lexer.next_token().value, Token::InterpStart);
+        assert_eq!(lexer.next_token().value, Token::Integer(1));
+        assert_eq!(lexer.next_token().value, Token::Plus);
+        assert_eq!(lexer.next_token().value, Token::Integer(2));
+        assert_eq!(lexer.next_token().value, Token::Error);
+        assert_eq!(lexer.next_token().value, Token::EOF);
+        let errors = lexer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, LexErrorKind::UnterminatedString);
+    }
 }
@@ -1,25 +1,62 @@
 
 pub mod token;
 pub mod lexer;
+pub mod reader_lexer;
+pub mod span;
+pub mod error;
 
+use std::io::{self, Read};
+
+use crate::error::LexError;
 use crate::lexer::Lexer;
-use crate::token::Token;
+use crate::reader_lexer::ReaderLexer;
+use crate::span::Spanned;
+use crate::token::{OwnedToken, Token};
 
 /// This is the main entry point for the Kāra compiler logic.
-/// It takes the source code as input and returns a vector of tokens.
-pub fn run_compiler(source: &str) -> Vec<Token> {
+/// It takes the source code as input and returns every token alongside any
+/// lexing diagnostics, so callers can report all of them at once instead of
+/// stopping at the first problem. Tokens borrow from `source`, so they
+/// cannot outlive it.
+pub fn run_compiler(source: &str) -> (Vec<Spanned<Token<'_>>>, Vec<LexError>) {
     let mut lexer = Lexer::new(source);
     let mut tokens = Vec::new();
 
     loop {
         let token = lexer.next_token();
-        let is_eof = token == Token::EOF;
+        let is_eof = token.value == Token::EOF;
+        tokens.push(token);
+        if is_eof {
+            break;
+        }
+    }
+    (tokens, lexer.into_errors())
+}
+
+/// Lexes `reader` incrementally off a small sliding buffer, rather than
+/// reading the whole stream into memory before scanning begins (see
+/// [`ReaderLexer`]).
+///
+/// Tokens here are [`OwnedToken`], not [`Token`]: `Token<'src>` borrows its
+/// lexemes straight out of the source (see its doc comment), which a true
+/// streaming lexer can't provide — its buffer gets overwritten on every
+/// refill, out from under any borrow into it. `run_compiler` keeps the
+/// zero-copy `Token` for sources already fully in memory; this entry point
+/// pays for owned `String`s on the reader path in exchange for not needing
+/// the whole stream in memory up front.
+pub fn run_compiler_reader<R: Read>(reader: R) -> io::Result<(Vec<Spanned<OwnedToken>>, Vec<LexError>)> {
+    let mut lexer = ReaderLexer::new(reader);
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token()?;
+        let is_eof = token.value == OwnedToken::EOF;
         tokens.push(token);
         if is_eof {
             break;
         }
     }
-    tokens
+    Ok((tokens, lexer.into_errors()))
 }
 
 #[cfg(test)]
@@ -41,35 +78,37 @@ mod tests {
             }
         "#;
 
-        let tokens = run_compiler(source);
+        let (tokens, errors) = run_compiler(source);
+        assert!(errors.is_empty(), "unexpected lexing errors: {errors:?}");
+        let values: Vec<Token> = tokens.into_iter().map(|t| t.value).collect();
 
         let expected_tokens = vec![
             // type UserId i64;
             Token::Type,
-            Token::Identifier("UserId".to_string()),
-            Token::Identifier("i64".to_string()),
+            Token::Identifier("UserId"),
+            Token::Identifier("i64"),
             Token::Semicolon,
 
             // flow PromoteUser(id: UserId) {
             Token::Flow,
-            Token::Identifier("PromoteUser".to_string()),
+            Token::Identifier("PromoteUser"),
             Token::LeftParen,
-            Token::Identifier("id".to_string()),
+            Token::Identifier("id"),
             Token::Colon,
-            Token::Identifier("UserId".to_string()),
+            Token::Identifier("UserId"),
             Token::RightParen,
             Token::LeftBrace,
 
             // let user_age = 30;
             Token::Let,
-            Token::Identifier("user_age".to_string()),
+            Token::Identifier("user_age"),
             Token::Equal,
             Token::Integer(30),
             Token::Semicolon,
 
             // if user_age >= 18 {
             Token::If,
-            Token::Identifier("user_age".to_string()),
+            Token::Identifier("user_age"),
             Token::GreaterThanOrEqual,
             Token::Integer(18),
             Token::LeftBrace,
@@ -81,6 +120,6 @@ mod tests {
             Token::EOF,
         ];
 
-        assert_eq!(tokens, expected_tokens, "The token stream did not match the expected output.");
+        assert_eq!(values, expected_tokens, "The token stream did not match the expected output.");
     }
 }
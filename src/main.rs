@@ -1,6 +1,5 @@
-mod lexer;
-
-use lexer::{Lexer, Token};
+use karac::lexer::Lexer;
+use karac::token::Token;
 
 fn main() {
     let source = r#"
@@ -14,7 +13,8 @@ Record Point {
 flow main {
     let p1 = Point { x: 10, y: 20 };
     
-    // This is the verbose way
+    // This is the verbose way. `Action`/`From` aren't keywords yet (see
+    // `Lexer::lookup_ident`), so these lex as plain identifiers.
     Action: PrintPoint
         From: p = p1;
 
@@ -23,16 +23,23 @@ flow main {
 }
 "#;
 
-    let mut lexer = Lexer::new(source.to_string());
+    let mut lexer = Lexer::new(source);
 
     println!("--- Lexer Output ---");
     loop {
         let token = lexer.next_token();
-        println!("{:?}", token);
-        if token == Token::EOF {
+        println!("{:?} @ {:?}", token.value, token.span);
+        if token.value == Token::EOF {
             break;
         }
     }
     println!("--- End Lexer Output ---");
 
+    let errors = lexer.into_errors();
+    if !errors.is_empty() {
+        println!("--- Lexer Errors ---");
+        for error in errors {
+            println!("{error:?}");
+        }
+    }
 }
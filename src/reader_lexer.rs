@@ -0,0 +1,145 @@
+// src/reader_lexer.rs
+
+//! A reader-backed lexer that scans a stream incrementally instead of
+//! requiring the whole source in memory up front.
+//!
+//! [`Lexer`] is zero-copy over a `&'src str`: its tokens borrow straight out
+//! of `source`, which is exactly why it can't be handed a [`Read`] stream
+//! directly. A true streaming lexer has to discard bytes behind the
+//! in-progress lexeme to keep its memory bounded, and a token that borrows
+//! from a buffer region about to be discarded is unsound. [`ReaderLexer`]
+//! resolves that by paying for an owned token ([`OwnedToken`]) on this path
+//! only: it scans a small sliding buffer with an ordinary [`Lexer`],
+//! converts each token to its owned form immediately, and only then drains
+//! the buffer up to the point the token ended.
+
+use std::io::{self, Read};
+
+use crate::error::LexError;
+use crate::lexer::{Lexer, LexerMode};
+use crate::span::Spanned;
+use crate::token::OwnedToken;
+
+/// Bytes requested per refill, and the sliding buffer's starting target
+/// size. Doubled (see [`ReaderLexer::next_token`]) whenever a lexeme turns
+/// out to span a refill boundary.
+const INITIAL_REFILL_TARGET: usize = 4096;
+
+/// Lexes an `impl Read` stream incrementally off a sliding buffer, rather
+/// than reading the whole stream into memory before scanning begins.
+pub struct ReaderLexer<R: Read> {
+    reader: R,
+    reader_eof: bool,
+    /// Bytes read from `reader` but not yet decoded into `buffer`, because
+    /// they end mid-UTF-8-sequence.
+    pending_bytes: Vec<u8>,
+    /// The still-unscanned tail of the stream read so far. Drained from the
+    /// front as tokens are emitted, and topped up from `reader` as needed.
+    buffer: String,
+    /// Global byte offset of `buffer`'s first byte in the whole stream, so
+    /// spans stay correct across drains.
+    base_offset: usize,
+    line: u32,
+    col: u32,
+    modes: Vec<LexerMode>,
+    errors: Vec<LexError>,
+}
+
+impl<R: Read> ReaderLexer<R> {
+    /// Creates a new `ReaderLexer` over `reader`. Nothing is read until the
+    /// first call to [`next_token`](Self::next_token).
+    pub fn new(reader: R) -> Self {
+        ReaderLexer {
+            reader,
+            reader_eof: false,
+            pending_bytes: Vec::new(),
+            buffer: String::new(),
+            base_offset: 0,
+            line: 1,
+            col: 1,
+            modes: vec![LexerMode::Normal],
+            errors: Vec::new(),
+        }
+    }
+
+    /// Reads from `reader` until `buffer` holds at least `min_len` bytes or
+    /// the stream is exhausted.
+    fn refill_to(&mut self, min_len: usize) -> io::Result<()> {
+        let mut chunk = [0u8; INITIAL_REFILL_TARGET];
+        while !self.reader_eof && self.buffer.len() < min_len {
+            let n = self.reader.read(&mut chunk)?;
+            if n == 0 {
+                self.reader_eof = true;
+                break;
+            }
+            self.pending_bytes.extend_from_slice(&chunk[..n]);
+
+            match std::str::from_utf8(&self.pending_bytes) {
+                Ok(valid) => {
+                    self.buffer.push_str(valid);
+                    self.pending_bytes.clear();
+                }
+                Err(err) => {
+                    let valid_len = err.valid_up_to();
+                    // Safety: `valid_up_to` guarantees this prefix is valid UTF-8.
+                    let valid = std::str::from_utf8(&self.pending_bytes[..valid_len]).unwrap();
+                    self.buffer.push_str(valid);
+                    self.pending_bytes.drain(..valid_len);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scans and returns the next token, paired with its span in the whole
+    /// stream. Returns `Err` only if reading from the underlying `reader`
+    /// fails; lexing problems are recorded as [`LexError`]s, same as
+    /// [`Lexer`].
+    pub fn next_token(&mut self) -> io::Result<Spanned<OwnedToken>> {
+        let mut target = INITIAL_REFILL_TARGET.max(self.buffer.len() + 1);
+        loop {
+            self.refill_to(target)?;
+
+            // `self.modes`/`self.errors` are only cloned in, never taken:
+            // if this attempt turns out to be boundary-truncated, it must
+            // leave no trace (no spurious diagnostic, no popped/truncated
+            // mode) for the retry to build on.
+            let mut lexer = Lexer::resume(&self.buffer, self.line, self.col, self.modes.clone(), self.errors.clone(), self.reader_eof);
+            let token = lexer.next_token();
+            let consumed = lexer.position();
+
+            if lexer.is_incomplete() {
+                // The scan ran off the end of the currently buffered bytes
+                // before it could tell whether the lexeme was actually
+                // finished. Discard the whole attempt — including any
+                // token, diagnostic, or mode-stack change it produced — and
+                // redo it from the unchanged pre-attempt state against a
+                // bigger buffer.
+                target *= 2;
+                continue;
+            }
+
+            let (line, col, modes, errors) = lexer.into_state();
+            self.line = line;
+            self.col = col;
+            self.modes = modes;
+            self.errors = errors;
+
+            let mut span = token.span;
+            span.start += self.base_offset;
+            span.end += self.base_offset;
+            let owned = Spanned::new(OwnedToken::from(&token.value), span);
+
+            self.base_offset += consumed;
+            self.buffer.drain(..consumed);
+
+            return Ok(owned);
+        }
+    }
+
+    /// Consumes the `ReaderLexer`, returning every diagnostic collected
+    /// while it ran.
+    pub fn into_errors(self) -> Vec<LexError> {
+        self.errors
+    }
+}
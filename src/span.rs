@@ -0,0 +1,26 @@
+// src/span.rs
+
+//! Source-location tracking shared by the lexer and, later, the parser.
+
+/// A half-open byte range `[start, end)` into the original source, plus the
+/// 1-based line/column of its first character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub col: u32,
+}
+
+/// A value paired with the span of source it was produced from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span) -> Self {
+        Spanned { value, span }
+    }
+}
@@ -2,8 +2,10 @@
 
 //! Defines the tokens that are produced by the lexer.
 
+use std::borrow::Cow;
+
 #[derive(Debug, PartialEq, Clone)]
-pub enum Token {
+pub enum Token<'src> {
     // Keywords
     Fn,
     Flow,
@@ -41,12 +43,118 @@ pub enum Token {
     Arrow,              // ->
 
     // Literals
+    //
+    // `Identifier` borrows straight out of the source. `StringLiteral` only
+    // owns a buffer when escape decoding forced one; a string with no
+    // escapes borrows its lexeme just like an identifier does.
+    Identifier(&'src str),
+    Integer(i64),
+    Float(f64),
+    StringLiteral(Cow<'src, str>),
+
+    /// Marks the `${` that opens an interpolated expression inside a
+    /// string literal; the tokens up to the matching `}` are the
+    /// expression, lexed normally.
+    InterpStart,
+
+    // Special Tokens
+    Error,
+    EOF,
+}
+
+/// An owned copy of [`Token`], holding `String`s instead of borrows into a
+/// source buffer.
+///
+/// [`crate::reader_lexer::ReaderLexer`] scans off a sliding buffer that gets
+/// overwritten on every refill, so its tokens can't borrow from `source`
+/// the way [`crate::lexer::Lexer`]'s do; this is the owned fallback it
+/// yields instead.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OwnedToken {
+    Fn,
+    Flow,
+    Record,
+    Type,
+    Let,
+    If,
+    True,
+    False,
+    As,
+
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Colon,
+    Comma,
+    Semicolon,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Dot,
+
+    Bang,
+    BangEqual,
+    Equal,
+    EqualEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Arrow,
+
     Identifier(String),
     Integer(i64),
     Float(f64),
     StringLiteral(String),
 
-    // Special Tokens
+    InterpStart,
+
     Error,
     EOF,
 }
+
+impl<'src> From<&Token<'src>> for OwnedToken {
+    fn from(token: &Token<'src>) -> Self {
+        match token {
+            Token::Fn => OwnedToken::Fn,
+            Token::Flow => OwnedToken::Flow,
+            Token::Record => OwnedToken::Record,
+            Token::Type => OwnedToken::Type,
+            Token::Let => OwnedToken::Let,
+            Token::If => OwnedToken::If,
+            Token::True => OwnedToken::True,
+            Token::False => OwnedToken::False,
+            Token::As => OwnedToken::As,
+            Token::LeftParen => OwnedToken::LeftParen,
+            Token::RightParen => OwnedToken::RightParen,
+            Token::LeftBrace => OwnedToken::LeftBrace,
+            Token::RightBrace => OwnedToken::RightBrace,
+            Token::Colon => OwnedToken::Colon,
+            Token::Comma => OwnedToken::Comma,
+            Token::Semicolon => OwnedToken::Semicolon,
+            Token::Plus => OwnedToken::Plus,
+            Token::Minus => OwnedToken::Minus,
+            Token::Star => OwnedToken::Star,
+            Token::Slash => OwnedToken::Slash,
+            Token::Dot => OwnedToken::Dot,
+            Token::Bang => OwnedToken::Bang,
+            Token::BangEqual => OwnedToken::BangEqual,
+            Token::Equal => OwnedToken::Equal,
+            Token::EqualEqual => OwnedToken::EqualEqual,
+            Token::GreaterThan => OwnedToken::GreaterThan,
+            Token::GreaterThanOrEqual => OwnedToken::GreaterThanOrEqual,
+            Token::LessThan => OwnedToken::LessThan,
+            Token::LessThanOrEqual => OwnedToken::LessThanOrEqual,
+            Token::Arrow => OwnedToken::Arrow,
+            Token::Identifier(s) => OwnedToken::Identifier((*s).to_string()),
+            Token::Integer(n) => OwnedToken::Integer(*n),
+            Token::Float(f) => OwnedToken::Float(*f),
+            Token::StringLiteral(s) => OwnedToken::StringLiteral(s.to_string()),
+            Token::InterpStart => OwnedToken::InterpStart,
+            Token::Error => OwnedToken::Error,
+            Token::EOF => OwnedToken::EOF,
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use karac::error::LexErrorKind;
 use karac::run_compiler;
-use karac::token::Token;
+use karac::run_compiler_reader;
+use karac::token::{OwnedToken, Token};
 
 #[test]
 fn test_lexer() {
@@ -13,49 +15,156 @@ fn test_lexer() {
         "hello world"
     "#;
 
-    let tokens = run_compiler(source);
+    let (spanned, errors) = run_compiler(source);
+    assert!(errors.is_empty(), "unexpected lexing errors: {errors:?}");
+    let tokens: Vec<Token> = spanned.into_iter().map(|t| t.value).collect();
 
     let expected_tokens = vec![
         Token::Let,
-        Token::Identifier("x".to_string()),
+        Token::Identifier("x"),
         Token::Equal,
-        Token::Number(5.0),
+        Token::Integer(5),
         Token::Semicolon,
         Token::Let,
-        Token::Identifier("y".to_string()),
+        Token::Identifier("y"),
         Token::Equal,
-        Token::Number(10.5),
+        Token::Float(10.5),
         Token::Semicolon,
         Token::Let,
-        Token::Identifier("add".to_string()),
+        Token::Identifier("add"),
         Token::Equal,
         Token::Fn,
         Token::LeftParen,
-        Token::Identifier("a".to_string()),
+        Token::Identifier("a"),
         Token::Comma,
-        Token::Identifier("b".to_string()),
+        Token::Identifier("b"),
         Token::RightParen,
         Token::Arrow,
         Token::LeftBrace,
-        Token::Identifier("a".to_string()),
+        Token::Identifier("a"),
         Token::Plus,
-        Token::Identifier("b".to_string()),
+        Token::Identifier("b"),
         Token::Semicolon,
         Token::RightBrace,
         Token::Semicolon,
         Token::Let,
-        Token::Identifier("result".to_string()),
+        Token::Identifier("result"),
         Token::Equal,
-        Token::Identifier("add".to_string()),
+        Token::Identifier("add"),
         Token::LeftParen,
-        Token::Identifier("x".to_string()),
+        Token::Identifier("x"),
         Token::Comma,
-        Token::Identifier("y".to_string()),
+        Token::Identifier("y"),
         Token::RightParen,
         Token::Semicolon,
-        Token::String("hello world".to_string()),
+        Token::StringLiteral("hello world".into()),
         Token::EOF,
     ];
 
     assert_eq!(tokens, expected_tokens);
 }
+
+#[test]
+fn test_unterminated_string_is_reported_without_stopping() {
+    let source = r#"let x = "oops; let y = 1;"#;
+
+    let (spanned, errors) = run_compiler(source);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, LexErrorKind::UnterminatedString);
+
+    // Lexing still reaches EOF instead of stopping at the bad string.
+    assert_eq!(spanned.last().map(|t| &t.value), Some(&Token::EOF));
+}
+
+#[test]
+fn test_run_compiler_reader_matches_run_compiler() {
+    let source = r#"let x = 5; "hello world""#;
+
+    let (spanned, errors) = run_compiler_reader(source.as_bytes()).expect("reading from a slice cannot fail");
+    assert!(errors.is_empty(), "unexpected lexing errors: {errors:?}");
+
+    let tokens: Vec<OwnedToken> = spanned.into_iter().map(|t| t.value).collect();
+    let expected_tokens = vec![
+        OwnedToken::Let,
+        OwnedToken::Identifier("x".to_string()),
+        OwnedToken::Equal,
+        OwnedToken::Integer(5),
+        OwnedToken::Semicolon,
+        OwnedToken::StringLiteral("hello world".to_string()),
+        OwnedToken::EOF,
+    ];
+
+    assert_eq!(tokens, expected_tokens);
+}
+
+#[test]
+fn test_run_compiler_reader_spans_a_refill_boundary() {
+    // Longer than the reader's initial sliding-buffer target, so the
+    // identifier and string below are forced to straddle at least one
+    // buffer refill.
+    let padding = "_".repeat(8192);
+    let source = format!(r#"let {padding} = "a string that also runs past the first refill boundary {padding}";"#);
+
+    let (spanned, errors) = run_compiler_reader(source.as_bytes()).expect("reading from a slice cannot fail");
+    assert!(errors.is_empty(), "unexpected lexing errors: {errors:?}");
+
+    let tokens: Vec<OwnedToken> = spanned.into_iter().map(|t| t.value).collect();
+    let expected_tokens = vec![
+        OwnedToken::Let,
+        OwnedToken::Identifier(padding.clone()),
+        OwnedToken::Equal,
+        OwnedToken::StringLiteral(format!("a string that also runs past the first refill boundary {padding}")),
+        OwnedToken::Semicolon,
+        OwnedToken::EOF,
+    ];
+
+    assert_eq!(tokens, expected_tokens);
+}
+
+/// A `Read` that only ever hands back `chunk_size` bytes per call, however
+/// much the caller asks for — standing in for a real file or socket, which
+/// `&[u8]`'s own `Read` impl (happy to fill the whole request in one call)
+/// doesn't exercise at all.
+struct ChunkedReader<'a> {
+    remaining: &'a [u8],
+    chunk_size: usize,
+}
+
+impl std::io::Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.remaining.len().min(buf.len()).min(self.chunk_size);
+        buf[..n].copy_from_slice(&self.remaining[..n]);
+        self.remaining = &self.remaining[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_run_compiler_reader_does_not_misreport_a_valid_string_read_in_small_chunks() {
+    // A string body long enough to outlast several small reads, so the
+    // in-progress lexeme really does straddle more than one refill.
+    let long_run = "a".repeat(10_000);
+    let source = format!(r#"let x = "{long_run}"; let y = 42;"#);
+
+    let reader = ChunkedReader { remaining: source.as_bytes(), chunk_size: 256 };
+    let (spanned, errors) = run_compiler_reader(reader).expect("reading from this stub cannot fail");
+    assert!(errors.is_empty(), "valid, closed string reported as unterminated: {errors:?}");
+
+    let tokens: Vec<OwnedToken> = spanned.into_iter().map(|t| t.value).collect();
+    let expected_tokens = vec![
+        OwnedToken::Let,
+        OwnedToken::Identifier("x".to_string()),
+        OwnedToken::Equal,
+        OwnedToken::StringLiteral(long_run),
+        OwnedToken::Semicolon,
+        OwnedToken::Let,
+        OwnedToken::Identifier("y".to_string()),
+        OwnedToken::Equal,
+        OwnedToken::Integer(42),
+        OwnedToken::Semicolon,
+        OwnedToken::EOF,
+    ];
+
+    assert_eq!(tokens, expected_tokens);
+}